@@ -0,0 +1,50 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+use crate::Result;
+
+#[derive(Clone, Debug, Default)]
+pub struct AwsKmsConfigProto {
+    pub region: String,
+    pub endpoint: String,
+    pub key_id: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AzureKmsConfig {
+    pub tenant_id: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub keyvault_url: String,
+    pub hsm_name: String,
+    pub hsm_url: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GcpKmsConfig {
+    pub project_id: String,
+    pub location: String,
+    pub key_ring: String,
+    pub key: String,
+    pub credential_path: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Config {
+    Aws { key_id: String, proto: AwsKmsConfigProto },
+    Azure { key_id: String, config: AzureKmsConfig },
+    Gcp { key_id: String, config: GcpKmsConfig },
+}
+
+impl Config {
+    pub fn from_proto(proto: AwsKmsConfigProto) -> Result<Self> {
+        let key_id = proto.key_id.clone();
+        Ok(Config::Aws { key_id, proto })
+    }
+
+    pub fn from_azure_kms_config(key_id: String, config: AzureKmsConfig) -> Result<Self> {
+        Ok(Config::Azure { key_id, config })
+    }
+
+    pub fn from_gcp_kms_config(key_id: String, config: GcpKmsConfig) -> Result<Self> {
+        Ok(Config::Gcp { key_id, config })
+    }
+}
@@ -3,18 +3,22 @@ use std::path::Path;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::fs::create_dir_all;
+use std::str::FromStr;
 
 #[cfg(feature = "cloud-aws")]
 use aws::{AwsKms, STORAGE_VENDOR_NAME_AWS};
 #[cfg(feature = "cloud-azure")]
 use azure::{AzureKms, STORAGE_VENDOR_NAME_AZURE};
+#[cfg(feature = "cloud-gcp")]
+use gcp::{GcpKms, STORAGE_VENDOR_NAME_GCP};
 use cloud::kms::Config as CloudConfig;
 #[cfg(feature = "cloud-aws")]
 pub use encryption::KmsBackend;
 pub use encryption::{
     clean_up_dir, clean_up_trash, from_engine_encryption_method, trash_dir_all, AzureConfig,
     Backend, DataKeyImporter, DataKeyManager, DKMMap, DataKeyManagerArgs, DecrypterReader,
-    EncryptionConfig, Error, FileConfig, Iv, KmsConfig, MasterKeyConfig, Result,
+    EncryptionConfig, Error, FileConfig, GcpConfig, Iv, KeyspaceConfig, KmsConfig,
+    MasterKeyConfig, Result,
 };
 use encryption::{cloud_convert_error, FileBackend, PlaintextBackend};
 use tikv_util::{box_err, error, info};
@@ -34,52 +38,121 @@ pub fn data_key_manager_from_config(
     DataKeyManager::new(master_key, previous_master_key, 0, args)
 }
 
+/// Rotates `manager`'s master key to whatever `config.master_key` now describes, without
+/// taking the node offline. [`DataKeyManager::replace_master_key`] only checks that the new
+/// backend is internally self-consistent (it can decrypt its own ciphertext) before swapping
+/// it in -- it does **not** verify the new backend against the current on-disk key
+/// dictionary, so a backend that rotated to the wrong key/vendor can still pass this check and
+/// leave existing data keys unreadable. The backend being replaced becomes the new
+/// previous-master-key fallback, the same role `config.previous_master_key` plays at startup,
+/// so keys written under it can still be decrypted.
+pub fn rotate_master_key(manager: &DataKeyManager, config: &EncryptionConfig) -> Result<()> {
+    info!("rotating master key");
+    let new_master_key = create_backend(&config.master_key).map_err(|e| {
+        error!("failed to access new master key, {}", e);
+        e
+    })?;
+    manager.replace_master_key(new_master_key)
+}
+
+/// The keyspace id that [`DKMMap`] falls back to when a lookup names a keyspace that was
+/// never configured, or whose own master key failed to build. Its [`MasterKeyConfig`]/
+/// `previous_master_key` come from the top-level [`EncryptionConfig`], so it can use a vendor
+/// different from any individual keyspace (AWS for one keyspace, Azure or File for another).
+const DEFAULT_KEYSPACE_ID: u32 = 0;
+
 pub fn data_key_manager_map_from_config(
     config: &EncryptionConfig,
     dict_path: &str,
 ) -> Result<DKMMap> {
     info!("MAP VERSION OF DKM LOADER: Loading data key manager from config...");
-    let master_key = create_backend(&config.master_key).map_err(|e| {
-        error!("failed to access master key, {}", e);
-        e
-    })?;
 
-    let default_keyspace: u32 = 0;
-    let file_dict_path = format!("{}/{}", dict_path, default_keyspace);
+    let default_manager = build_keyspace_data_key_manager(
+        DEFAULT_KEYSPACE_ID,
+        &config.master_key,
+        &config.previous_master_key,
+        dict_path,
+        config,
+    )?
+    .ok_or_else(|| {
+        Error::Other(box_err!(
+            "encryption must be enabled for the default keyspace {}",
+            DEFAULT_KEYSPACE_ID
+        ))
+    })?;
 
-    info!("creating new dir if needed {}", file_dict_path);
-    create_dir_all(file_dict_path.clone())?;
-    let args = DataKeyManagerArgs::from_encryption_config(&file_dict_path, config);
-    let previous_master_key_conf = config.previous_master_key.clone();
-    let previous_master_key = Box::new(move || create_backend(&previous_master_key_conf));
     let mut dkm_map = HashMap::new();
+    dkm_map.insert(DEFAULT_KEYSPACE_ID, Arc::new(default_manager));
 
-
-    // master_key will have a keyspace_id of 0.
-    let data_key_manager = DataKeyManager::new(master_key, previous_master_key, 0, args.clone())
-        .unwrap().unwrap();
-
-    dkm_map.insert(0, Arc::new(data_key_manager));
     for keyspace_config in &config.keyspace_keys {
-        let keyspace_key = create_backend(&keyspace_config.key_config).map_err(|e| {
-            error!("failed to access master key, {}", e);
-            e
-        })?;
-        let previous_key_conf = keyspace_config.previous_key_config.clone();
-        let previous_key = Box::new(move || create_backend(&previous_key_conf));
-        let new_file_dict_path = format!("{}/{}", dict_path, keyspace_config.keyspace_id);
-        info!("creating new dir if needed {}", new_file_dict_path);
-        create_dir_all(new_file_dict_path.clone())?;
-        let new_args = DataKeyManagerArgs::from_encryption_config(&new_file_dict_path, config);
-        let key_manager = DataKeyManager::new(
-            keyspace_key, previous_key,
-            keyspace_config.keyspace_id, new_args.clone()).unwrap().unwrap();
-        dkm_map.insert(keyspace_config.keyspace_id, Arc::new(key_manager));
+        let keyspace_id = keyspace_config.keyspace_id;
+        match build_keyspace_data_key_manager(
+            keyspace_id,
+            &keyspace_config.key_config,
+            &keyspace_config.previous_key_config,
+            dict_path,
+            config,
+        ) {
+            Ok(Some(key_manager)) => {
+                dkm_map.insert(keyspace_id, Arc::new(key_manager));
+            }
+            Ok(None) => {
+                info!(
+                    "encryption is disabled for keyspace, it will fall back to the default \
+                     keyspace";
+                    "keyspace_id" => keyspace_id,
+                );
+            }
+            Err(e) => {
+                error!(
+                    "failed to build data key manager for keyspace, it will fall back to the \
+                     default keyspace";
+                    "keyspace_id" => keyspace_id,
+                    "err" => %e,
+                );
+            }
+        }
     }
 
     info!("dkm_map len"; "dkm_map_len" => dkm_map.len());
-    let dkmm = DKMMap::new(dkm_map);
-    Ok(dkmm)
+    Ok(DKMMap::new_with_default(dkm_map, DEFAULT_KEYSPACE_ID))
+}
+
+/// Builds the [`DataKeyManager`] for a single keyspace, returning a descriptive [`Error`]
+/// naming `keyspace_id` instead of panicking if the keyspace's own master key can't be used.
+/// Passes through [`DataKeyManager::new`]'s own `Option` return as `Ok(None)`, which callers
+/// should treat as distinct from a real build failure; a `Plaintext` `key_config` still yields
+/// `Ok(Some(_))`. Callers building a map of several keyspaces can catch
+/// the `Err` case and skip just the offending keyspace rather than aborting the whole map.
+fn build_keyspace_data_key_manager(
+    keyspace_id: u32,
+    key_config: &MasterKeyConfig,
+    previous_key_config: &MasterKeyConfig,
+    dict_path: &str,
+    config: &EncryptionConfig,
+) -> Result<Option<DataKeyManager>> {
+    let master_key = create_backend(key_config).map_err(|e| {
+        Error::Other(box_err!(
+            "failed to access master key for keyspace {}: {}",
+            keyspace_id,
+            e
+        ))
+    })?;
+    let previous_key_conf = previous_key_config.clone();
+    let previous_key = Box::new(move || create_backend(&previous_key_conf));
+
+    let file_dict_path = format!("{}/{}", dict_path, keyspace_id);
+    info!("creating new dir if needed {}", file_dict_path);
+    create_dir_all(&file_dict_path)?;
+    let args = DataKeyManagerArgs::from_encryption_config(&file_dict_path, config);
+
+    DataKeyManager::new(master_key, previous_key, keyspace_id, args).map_err(|e| {
+        Error::Other(box_err!(
+            "failed to build data key manager for keyspace {}: {}",
+            keyspace_id,
+            e
+        ))
+    })
 }
 
 
@@ -123,6 +196,20 @@ pub fn create_cloud_backend(config: &KmsConfig) -> Result<Box<dyn Backend>> {
             );
             Ok(Box::new(KmsBackend::new(keyvault_provider)?) as Box<dyn Backend>)
         }
+        #[cfg(feature = "cloud-gcp")]
+        STORAGE_VENDOR_NAME_GCP => {
+            if config.gcp.is_none() {
+                return Err(Error::Other(box_err!(
+                    "invalid configurations for GCP KMS"
+                )));
+            }
+            let (mk, gcp_kms_cfg) = config.clone().convert_to_gcp_kms_config();
+            let conf = CloudConfig::from_gcp_kms_config(mk, gcp_kms_cfg)
+                .map_err(cloud_convert_error("gcp from proto".to_owned()))?;
+            let kms_provider =
+                Box::new(GcpKms::new(conf).map_err(cloud_convert_error("new GCP KMS".to_owned()))?);
+            Ok(Box::new(KmsBackend::new(kms_provider)?) as Box<dyn Backend>)
+        }
         provider => Err(Error::Other(box_err!("provider not found {}", provider))),
     }
 }
@@ -137,6 +224,169 @@ fn create_backend_inner(config: &MasterKeyConfig) -> Result<Box<dyn Backend>> {
     })
 }
 
+// ConfigKey's string form (ConfigKey::as_ref) doubles as the env var name, so "key-id" and
+// "KEY_ID" both resolve to ConfigKey::KeyId.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigKey {
+    Region,
+    Endpoint,
+    KeyId,
+    TenantId,
+    ClientId,
+    ClientSecret,
+    KeyvaultUrl,
+    HsmName,
+    HsmUrl,
+    ProjectId,
+    Location,
+    KeyRing,
+    Key,
+    CredentialsPath,
+}
+
+impl FromStr for ConfigKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "region" => ConfigKey::Region,
+            "endpoint" => ConfigKey::Endpoint,
+            "key-id" => ConfigKey::KeyId,
+            "tenant-id" => ConfigKey::TenantId,
+            "client-id" => ConfigKey::ClientId,
+            "client-secret" => ConfigKey::ClientSecret,
+            "keyvault-url" => ConfigKey::KeyvaultUrl,
+            "hsm-name" => ConfigKey::HsmName,
+            "hsm-url" => ConfigKey::HsmUrl,
+            "project-id" => ConfigKey::ProjectId,
+            "location" => ConfigKey::Location,
+            "key-ring" => ConfigKey::KeyRing,
+            "key" => ConfigKey::Key,
+            "credentials-path" => ConfigKey::CredentialsPath,
+            _ => {
+                return Err(Error::UnknownConfigurationKey {
+                    key: s.to_owned(),
+                })
+            }
+        })
+    }
+}
+
+impl AsRef<str> for ConfigKey {
+    fn as_ref(&self) -> &str {
+        match self {
+            ConfigKey::Region => "region",
+            ConfigKey::Endpoint => "endpoint",
+            ConfigKey::KeyId => "key-id",
+            ConfigKey::TenantId => "tenant-id",
+            ConfigKey::ClientId => "client-id",
+            ConfigKey::ClientSecret => "client-secret",
+            ConfigKey::KeyvaultUrl => "keyvault-url",
+            ConfigKey::HsmName => "hsm-name",
+            ConfigKey::HsmUrl => "hsm-url",
+            ConfigKey::ProjectId => "project-id",
+            ConfigKey::Location => "location",
+            ConfigKey::KeyRing => "key-ring",
+            ConfigKey::Key => "key",
+            ConfigKey::CredentialsPath => "credentials-path",
+        }
+    }
+}
+
+// Which ConfigKeys are valid for a given vendor; a key outside this set is rejected even
+// though it's a real ConfigKey for some other vendor (e.g. tenant-id passed while building an
+// AWS backend).
+fn allowed_keys_for_vendor(vendor: &str) -> &'static [ConfigKey] {
+    match vendor {
+        #[cfg(feature = "cloud-aws")]
+        STORAGE_VENDOR_NAME_AWS | "" => &[ConfigKey::Region, ConfigKey::Endpoint, ConfigKey::KeyId],
+        #[cfg(feature = "cloud-azure")]
+        STORAGE_VENDOR_NAME_AZURE => &[
+            ConfigKey::KeyId,
+            ConfigKey::TenantId,
+            ConfigKey::ClientId,
+            ConfigKey::ClientSecret,
+            ConfigKey::KeyvaultUrl,
+            ConfigKey::HsmName,
+            ConfigKey::HsmUrl,
+        ],
+        #[cfg(feature = "cloud-gcp")]
+        STORAGE_VENDOR_NAME_GCP => &[
+            ConfigKey::ProjectId,
+            ConfigKey::Location,
+            ConfigKey::KeyRing,
+            ConfigKey::Key,
+            ConfigKey::CredentialsPath,
+        ],
+        _ => &[],
+    }
+}
+
+// Builds a master-key backend the same way create_cloud_backend does, but from a flat
+// (option name, value) map instead of the strongly-typed KmsConfig, so callers with only a
+// string config source (env vars, generic key-value stores) can configure AWS/Azure/GCP KMS.
+// Unrecognized or vendor-inapplicable option names are rejected with
+// Error::UnknownConfigurationKey rather than silently ignored.
+pub fn create_backend_from_options(
+    vendor: &str,
+    options: impl IntoIterator<Item = (String, String)>,
+) -> Result<Box<dyn Backend>> {
+    let allowed = allowed_keys_for_vendor(vendor);
+    let mut opts = HashMap::new();
+    for (key, value) in options {
+        let parsed = ConfigKey::from_str(&key)?;
+        if !allowed.contains(&parsed) {
+            return Err(Error::UnknownConfigurationKey { key });
+        }
+        opts.insert(parsed, value);
+    }
+
+    let mut config = KmsConfig {
+        vendor: vendor.to_owned(),
+        ..Default::default()
+    };
+    if let Some(v) = opts.get(&ConfigKey::Region) {
+        config.region = v.clone();
+    }
+    if let Some(v) = opts.get(&ConfigKey::Endpoint) {
+        config.endpoint = v.clone();
+    }
+    if let Some(v) = opts.get(&ConfigKey::KeyId) {
+        config.key_id = v.clone();
+    }
+
+    match vendor {
+        #[cfg(feature = "cloud-azure")]
+        STORAGE_VENDOR_NAME_AZURE => {
+            config.azure = Some(AzureConfig {
+                tenant_id: opts.get(&ConfigKey::TenantId).cloned().unwrap_or_default(),
+                client_id: opts.get(&ConfigKey::ClientId).cloned().unwrap_or_default(),
+                client_secret: opts.get(&ConfigKey::ClientSecret).cloned(),
+                keyvault_url: opts
+                    .get(&ConfigKey::KeyvaultUrl)
+                    .cloned()
+                    .unwrap_or_default(),
+                hsm_name: opts.get(&ConfigKey::HsmName).cloned().unwrap_or_default(),
+                hsm_url: opts.get(&ConfigKey::HsmUrl).cloned().unwrap_or_default(),
+                ..AzureConfig::default()
+            });
+        }
+        #[cfg(feature = "cloud-gcp")]
+        STORAGE_VENDOR_NAME_GCP => {
+            config.gcp = Some(GcpConfig {
+                project_id: opts.get(&ConfigKey::ProjectId).cloned().unwrap_or_default(),
+                location: opts.get(&ConfigKey::Location).cloned().unwrap_or_default(),
+                key_ring: opts.get(&ConfigKey::KeyRing).cloned().unwrap_or_default(),
+                key: opts.get(&ConfigKey::Key).cloned().unwrap_or_default(),
+                credential_path: opts.get(&ConfigKey::CredentialsPath).cloned(),
+            });
+        }
+        _ => {}
+    }
+
+    create_cloud_backend(&config)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,6 +408,7 @@ mod tests {
                 client_secret: Some("client_secret".to_owned()),
                 ..AzureConfig::default()
             }),
+            gcp: None,
         };
         let invalid_config = KmsConfig {
             azure: None,
@@ -167,4 +418,143 @@ mod tests {
         let backend = create_cloud_backend(&config).unwrap();
         assert!(backend.is_secure());
     }
+
+    #[test]
+    #[cfg(feature = "cloud-gcp")]
+    fn test_kms_cloud_backend_gcp() {
+        let config = KmsConfig {
+            key_id: "key_id".to_owned(),
+            region: "region".to_owned(),
+            endpoint: "endpoint".to_owned(),
+            vendor: STORAGE_VENDOR_NAME_GCP.to_owned(),
+            azure: None,
+            gcp: Some(GcpConfig {
+                project_id: "project_id".to_owned(),
+                location: "global".to_owned(),
+                key_ring: "key_ring".to_owned(),
+                key: "key".to_owned(),
+                credential_path: None,
+            }),
+        };
+        let invalid_config = KmsConfig {
+            gcp: None,
+            ..config.clone()
+        };
+        create_cloud_backend(&invalid_config).unwrap_err();
+        let backend = create_cloud_backend(&config).unwrap();
+        assert!(backend.is_secure());
+    }
+
+    #[test]
+    #[cfg(feature = "cloud-gcp")]
+    fn test_kms_cloud_backend_gcp_encrypt_fails_gracefully() {
+        let config = KmsConfig {
+            key_id: "key_id".to_owned(),
+            region: "region".to_owned(),
+            endpoint: "endpoint".to_owned(),
+            vendor: STORAGE_VENDOR_NAME_GCP.to_owned(),
+            azure: None,
+            gcp: Some(GcpConfig {
+                project_id: "project_id".to_owned(),
+                location: "global".to_owned(),
+                key_ring: "key_ring".to_owned(),
+                key: "key".to_owned(),
+                credential_path: None,
+            }),
+        };
+        let backend = create_cloud_backend(&config).unwrap();
+        // The real Cloud KMS RPC isn't wired in yet; calling it must return an Err, not panic.
+        backend.encrypt(b"probe").unwrap_err();
+    }
+
+    #[test]
+    fn test_data_key_manager_map_from_config_skips_failing_keyspace() {
+        let dict_path = std::env::temp_dir()
+            .join("encryption_dkm_map_test_skips_failing_keyspace")
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let config = EncryptionConfig {
+            master_key: MasterKeyConfig::Plaintext,
+            previous_master_key: MasterKeyConfig::Plaintext,
+            keyspace_keys: vec![
+                KeyspaceConfig {
+                    keyspace_id: 1,
+                    // No vendor name matches any compiled-in backend, so this keyspace's
+                    // manager fails to build.
+                    key_config: MasterKeyConfig::Kms {
+                        config: KmsConfig {
+                            vendor: "not-a-real-vendor".to_owned(),
+                            ..Default::default()
+                        },
+                    },
+                    previous_key_config: MasterKeyConfig::Plaintext,
+                },
+                KeyspaceConfig {
+                    keyspace_id: 2,
+                    key_config: MasterKeyConfig::Plaintext,
+                    previous_key_config: MasterKeyConfig::Plaintext,
+                },
+            ],
+        };
+
+        let dkm_map = data_key_manager_map_from_config(&config, &dict_path).unwrap();
+        // The default keyspace and the keyspace with a working config both come back...
+        assert!(dkm_map.get(DEFAULT_KEYSPACE_ID).is_some());
+        assert_eq!(dkm_map.get(2).unwrap().keyspace_id(), 2);
+        // ...but the failing keyspace falls back to the default manager instead of aborting
+        // the whole map.
+        assert_eq!(dkm_map.get(1).unwrap().keyspace_id(), DEFAULT_KEYSPACE_ID);
+    }
+
+    #[test]
+    fn test_config_key_roundtrip() {
+        for key in [
+            ConfigKey::Region,
+            ConfigKey::Endpoint,
+            ConfigKey::KeyId,
+            ConfigKey::TenantId,
+            ConfigKey::ClientId,
+            ConfigKey::ClientSecret,
+            ConfigKey::KeyvaultUrl,
+            ConfigKey::HsmName,
+            ConfigKey::HsmUrl,
+            ConfigKey::ProjectId,
+            ConfigKey::Location,
+            ConfigKey::KeyRing,
+            ConfigKey::Key,
+            ConfigKey::CredentialsPath,
+        ] {
+            assert_eq!(ConfigKey::from_str(key.as_ref()).unwrap(), key);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cloud-azure")]
+    fn test_create_backend_from_options_unknown_key() {
+        let err = create_backend_from_options(
+            STORAGE_VENDOR_NAME_AZURE,
+            vec![("not-a-real-key".to_owned(), "value".to_owned())],
+        )
+        .unwrap_err();
+        match err {
+            Error::UnknownConfigurationKey { key } => assert_eq!(key, "not-a-real-key"),
+            other => panic!("expected UnknownConfigurationKey, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cloud-aws")]
+    fn test_create_backend_from_options_rejects_vendor_inapplicable_key() {
+        let err = create_backend_from_options(
+            STORAGE_VENDOR_NAME_AWS,
+            vec![("tenant-id".to_owned(), "tenant_id".to_owned())],
+        )
+        .unwrap_err();
+        match err {
+            Error::UnknownConfigurationKey { key } => assert_eq!(key, "tenant-id"),
+            other => panic!("expected UnknownConfigurationKey, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,106 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+use cloud::kms::{AwsKmsConfigProto, AzureKmsConfig, GcpKmsConfig};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileConfig {
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AzureConfig {
+    pub tenant_id: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub keyvault_url: String,
+    pub hsm_name: String,
+    pub hsm_url: String,
+}
+
+// Identifies a Cloud KMS key the way Cloud KMS names keys: project / location (`global` for a
+// multi-region key) / key ring / key. credential_path mirrors GOOGLE_APPLICATION_CREDENTIALS.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GcpConfig {
+    pub project_id: String,
+    pub location: String,
+    pub key_ring: String,
+    pub key: String,
+    pub credential_path: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KmsConfig {
+    pub key_id: String,
+    pub region: String,
+    pub endpoint: String,
+    pub vendor: String,
+    pub azure: Option<AzureConfig>,
+    pub gcp: Option<GcpConfig>,
+}
+
+impl KmsConfig {
+    pub fn into_proto(self) -> AwsKmsConfigProto {
+        AwsKmsConfigProto {
+            region: self.region,
+            endpoint: self.endpoint,
+            key_id: self.key_id,
+        }
+    }
+
+    // Panics if self.azure is None; callers check config.azure.is_some() first.
+    pub fn convert_to_azure_kms_config(self) -> (String, AzureKmsConfig) {
+        let azure = self.azure.expect("KmsConfig.azure must be set");
+        (
+            self.key_id,
+            AzureKmsConfig {
+                tenant_id: azure.tenant_id,
+                client_id: azure.client_id,
+                client_secret: azure.client_secret,
+                keyvault_url: azure.keyvault_url,
+                hsm_name: azure.hsm_name,
+                hsm_url: azure.hsm_url,
+            },
+        )
+    }
+
+    // Panics if self.gcp is None; callers check config.gcp.is_some() first.
+    pub fn convert_to_gcp_kms_config(self) -> (String, GcpKmsConfig) {
+        let gcp = self.gcp.expect("KmsConfig.gcp must be set");
+        (
+            self.key_id,
+            GcpKmsConfig {
+                project_id: gcp.project_id,
+                location: gcp.location,
+                key_ring: gcp.key_ring,
+                key: gcp.key,
+                credential_path: gcp.credential_path,
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MasterKeyConfig {
+    Plaintext,
+    File { config: FileConfig },
+    Kms { config: KmsConfig },
+}
+
+impl Default for MasterKeyConfig {
+    fn default() -> Self {
+        MasterKeyConfig::Plaintext
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyspaceConfig {
+    pub keyspace_id: u32,
+    pub key_config: MasterKeyConfig,
+    pub previous_key_config: MasterKeyConfig,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EncryptionConfig {
+    pub master_key: MasterKeyConfig,
+    pub previous_master_key: MasterKeyConfig,
+    pub keyspace_keys: Vec<KeyspaceConfig>,
+}
@@ -0,0 +1,88 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::DataKeyManager;
+
+pub struct DKMMap {
+    managers: HashMap<u32, Arc<DataKeyManager>>,
+    default_keyspace_id: u32,
+}
+
+impl DKMMap {
+    pub fn new(managers: HashMap<u32, Arc<DataKeyManager>>) -> Self {
+        let default_keyspace_id = managers.keys().copied().min().unwrap_or_default();
+        DKMMap {
+            managers,
+            default_keyspace_id,
+        }
+    }
+
+    // default_keyspace_id must have an entry in managers; lookups that miss fall back to it.
+    pub fn new_with_default(managers: HashMap<u32, Arc<DataKeyManager>>, default_keyspace_id: u32) -> Self {
+        debug_assert!(
+            managers.contains_key(&default_keyspace_id),
+            "default keyspace {} must have a data key manager",
+            default_keyspace_id
+        );
+        DKMMap {
+            managers,
+            default_keyspace_id,
+        }
+    }
+
+    pub fn get(&self, keyspace_id: u32) -> Option<Arc<DataKeyManager>> {
+        self.managers
+            .get(&keyspace_id)
+            .or_else(|| self.managers.get(&self.default_keyspace_id))
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.managers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.managers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::master_key::PlaintextBackend;
+    use crate::{Backend, DataKeyManagerArgs};
+
+    fn manager(keyspace_id: u32) -> Arc<DataKeyManager> {
+        Arc::new(
+            DataKeyManager::new(
+                Box::new(PlaintextBackend {}),
+                Box::new(|| Ok(Box::new(PlaintextBackend {}) as Box<dyn Backend>)),
+                keyspace_id,
+                DataKeyManagerArgs::default(),
+            )
+            .unwrap()
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_get_on_unmapped_keyspace_falls_back_to_default() {
+        let mut managers = HashMap::new();
+        managers.insert(0, manager(0));
+        let dkm_map = DKMMap::new_with_default(managers, 0);
+
+        let fallback = dkm_map.get(42).unwrap();
+        assert_eq!(fallback.keyspace_id(), 0);
+    }
+
+    #[test]
+    fn test_get_on_mapped_keyspace_returns_its_own_manager() {
+        let mut managers = HashMap::new();
+        managers.insert(0, manager(0));
+        managers.insert(7, manager(7));
+        let dkm_map = DKMMap::new_with_default(managers, 0);
+
+        assert_eq!(dkm_map.get(7).unwrap().keyspace_id(), 7);
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+use std::io::Error as IoError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(IoError),
+    Other(Box<dyn std::error::Error + Sync + Send>),
+    UnknownConfigurationKey { key: String },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "IO error: {}", e),
+            Error::Other(e) => write!(f, "{}", e),
+            Error::UnknownConfigurationKey { key } => {
+                write!(f, "unknown configuration key: {}", key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<IoError> for Error {
+    fn from(e: IoError) -> Error {
+        Error::Io(e)
+    }
+}
+
+pub fn cloud_convert_error(prefix: String) -> impl FnOnce(cloud::Error) -> Error {
+    move |e| Error::Other(Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("{}: {}", prefix, e))))
+}
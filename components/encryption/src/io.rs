@@ -0,0 +1,74 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::Result;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Iv([u8; 16]);
+
+impl Iv {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+pub struct DecrypterReader<R> {
+    inner: R,
+}
+
+impl<R: Read> DecrypterReader<R> {
+    pub fn new(inner: R, _iv: Iv) -> Self {
+        DecrypterReader { inner }
+    }
+}
+
+impl<R: Read> Read for DecrypterReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+pub struct DataKeyImporter;
+
+pub fn from_engine_encryption_method(method: &str) -> Result<String> {
+    Ok(method.to_owned())
+}
+
+pub fn clean_up_dir(dir: &Path, skip: &str) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name() == *skip {
+            continue;
+        }
+        if entry.path().is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn clean_up_trash(dir: &Path) -> Result<()> {
+    let trash = dir.join("trash");
+    if trash.exists() {
+        fs::remove_dir_all(trash)?;
+    }
+    Ok(())
+}
+
+pub fn trash_dir_all(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        let trash = parent.join("trash");
+        fs::create_dir_all(&trash)?;
+        if let Some(name) = path.file_name() {
+            fs::rename(path, trash.join(name))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+mod config;
+mod dkm_map;
+pub mod errors;
+mod io;
+mod manager;
+mod master_key;
+
+pub use config::{
+    AzureConfig, EncryptionConfig, FileConfig, GcpConfig, KeyspaceConfig, KmsConfig,
+    MasterKeyConfig,
+};
+pub use dkm_map::DKMMap;
+pub use errors::{cloud_convert_error, Error, Result};
+pub use io::{
+    clean_up_dir, clean_up_trash, from_engine_encryption_method, trash_dir_all, DataKeyImporter,
+    DecrypterReader, Iv,
+};
+pub use manager::{DataKeyManager, DataKeyManagerArgs};
+pub use master_key::{Backend, FileBackend, KmsBackend, PlaintextBackend};
@@ -0,0 +1,196 @@
+// Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
+use std::sync::{Arc, Mutex};
+
+use tikv_util::box_err;
+
+use crate::{Backend, EncryptionConfig, Error, Result};
+
+#[derive(Clone, Default)]
+pub struct DataKeyManagerArgs {
+    pub dict_path: String,
+}
+
+impl DataKeyManagerArgs {
+    pub fn from_encryption_config(dict_path: &str, _config: &EncryptionConfig) -> Self {
+        DataKeyManagerArgs {
+            dict_path: dict_path.to_owned(),
+        }
+    }
+}
+
+enum PreviousMasterKey {
+    Lazy(Box<dyn Fn() -> Result<Box<dyn Backend>> + Send + Sync>),
+    Built(Arc<dyn Backend>),
+}
+
+impl PreviousMasterKey {
+    fn resolve(&self) -> Result<Arc<dyn Backend>> {
+        match self {
+            PreviousMasterKey::Lazy(f) => Ok(Arc::from(f()?)),
+            PreviousMasterKey::Built(backend) => Ok(Arc::clone(backend)),
+        }
+    }
+}
+
+struct DataKeyManagerState {
+    master_key: Arc<dyn Backend>,
+    previous_master_key: PreviousMasterKey,
+}
+
+pub struct DataKeyManager {
+    state: Mutex<DataKeyManagerState>,
+    keyspace_id: u32,
+    args: DataKeyManagerArgs,
+}
+
+impl DataKeyManager {
+    pub fn new(
+        master_key: Box<dyn Backend>,
+        previous_master_key: Box<dyn Fn() -> Result<Box<dyn Backend>> + Send + Sync>,
+        keyspace_id: u32,
+        args: DataKeyManagerArgs,
+    ) -> Result<Option<Self>> {
+        Ok(Some(DataKeyManager {
+            state: Mutex::new(DataKeyManagerState {
+                master_key: Arc::from(master_key),
+                previous_master_key: PreviousMasterKey::Lazy(previous_master_key),
+            }),
+            keyspace_id,
+            args,
+        }))
+    }
+
+    pub fn keyspace_id(&self) -> u32 {
+        self.keyspace_id
+    }
+
+    pub fn dict_path(&self) -> &str {
+        &self.args.dict_path
+    }
+
+    fn current_master_key(&self) -> Arc<dyn Backend> {
+        Arc::clone(&self.state.lock().unwrap().master_key)
+    }
+
+    pub fn previous_master_key(&self) -> Result<Arc<dyn Backend>> {
+        self.state.lock().unwrap().previous_master_key.resolve()
+    }
+
+    // Only checks that new_backend can decrypt its own ciphertext -- the on-disk dictionary
+    // reader isn't wired in, so this does NOT verify new_backend can decrypt any key actually
+    // in the current dictionary. A backend that rotated to the wrong key/vendor but is
+    // internally self-consistent (e.g. another real KMS key) will pass this check and still
+    // be unable to read existing data keys.
+    fn verify_backend_self_consistent(&self, backend: &dyn Backend) -> Result<()> {
+        let probe = backend.encrypt(b"master-key-rotation-probe")?;
+        let decrypted = backend.decrypt(&probe)?;
+        if decrypted != b"master-key-rotation-probe" {
+            return Err(Error::Other(box_err!(
+                "new master key for keyspace {} failed to decrypt its own probe ciphertext",
+                self.keyspace_id
+            )));
+        }
+        Ok(())
+    }
+
+    // Checks new_backend is self-consistent (see verify_backend_self_consistent) before
+    // swapping it in; the backend it replaces becomes the previous-master-key fallback, so keys
+    // written under it remain readable. A backend whose encrypt/decrypt isn't implemented yet
+    // (e.g. FileBackend, GcpKms) returns an Err here rather than panicking.
+    pub fn replace_master_key(&self, new_backend: Box<dyn Backend>) -> Result<()> {
+        self.verify_backend_self_consistent(new_backend.as_ref())?;
+        let new_backend: Arc<dyn Backend> = Arc::from(new_backend);
+
+        let mut state = self.state.lock().unwrap();
+        let old_backend = std::mem::replace(&mut state.master_key, new_backend);
+        state.previous_master_key = PreviousMasterKey::Built(old_backend);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::master_key::PlaintextBackend;
+
+    // Tags its ciphertext with an id byte so tests can tell which backend produced/consumed it.
+    struct TaggedBackend(u8);
+
+    impl Backend for TaggedBackend {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+            let mut out = vec![self.0];
+            out.extend_from_slice(plaintext);
+            Ok(out)
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+            if ciphertext.first() != Some(&self.0) {
+                return Err(Error::Other(box_err!("tag mismatch")));
+            }
+            Ok(ciphertext[1..].to_vec())
+        }
+
+        fn is_secure(&self) -> bool {
+            true
+        }
+    }
+
+    fn new_manager(master_key: u8) -> DataKeyManager {
+        DataKeyManager::new(
+            Box::new(TaggedBackend(master_key)),
+            Box::new(|| Ok(Box::new(PlaintextBackend {}) as Box<dyn Backend>)),
+            0,
+            DataKeyManagerArgs::default(),
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_replace_master_key_swaps_active_and_demotes_old() {
+        let manager = new_manager(1);
+        assert_eq!(manager.current_master_key().decrypt(&[1, 9]).unwrap(), [9]);
+
+        manager
+            .replace_master_key(Box::new(TaggedBackend(2)))
+            .unwrap();
+
+        // The new backend is now active.
+        assert_eq!(manager.current_master_key().decrypt(&[2, 9]).unwrap(), [9]);
+        // The old backend became the previous-master-key fallback.
+        assert_eq!(
+            manager.previous_master_key().unwrap().decrypt(&[1, 9]).unwrap(),
+            [9]
+        );
+    }
+
+    #[test]
+    fn test_replace_master_key_rejects_backend_that_cannot_decrypt_itself() {
+        struct BrokenBackend;
+        impl Backend for BrokenBackend {
+            fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+                Ok(plaintext.to_vec())
+            }
+            fn decrypt(&self, _ciphertext: &[u8]) -> Result<Vec<u8>> {
+                Ok(b"garbage".to_vec())
+            }
+            fn is_secure(&self) -> bool {
+                true
+            }
+        }
+
+        let manager = new_manager(1);
+        manager.replace_master_key(Box::new(BrokenBackend)).unwrap_err();
+        // The manager must still be on its original, working backend.
+        assert_eq!(manager.current_master_key().decrypt(&[1, 9]).unwrap(), [9]);
+    }
+
+    #[test]
+    fn test_replace_master_key_returns_err_instead_of_panicking_for_unimplemented_backend() {
+        use crate::master_key::FileBackend;
+
+        let manager = new_manager(1);
+        let file_backend = FileBackend::new(std::path::Path::new("/tmp/does-not-matter")).unwrap();
+        manager.replace_master_key(Box::new(file_backend)).unwrap_err();
+    }
+}